@@ -13,15 +13,18 @@
 // limitations under the License.
 //
 
+use std::convert::TryFrom;
+use std::error::Error;
 use std::fmt::{self, Display};
 use std::ops::Deref;
 use std::str;
+use std::str::FromStr;
 use std::sync::Mutex;
 
 use rand::distributions::Alphanumeric;
 use rand::rngs::OsRng;
-use rand::thread_rng;
 use rand::Rng;
+use rand::RngCore;
 
 const BASE: usize = 62;
 const ALPHABET: [u8; BASE] = *b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
@@ -43,20 +46,45 @@ pub fn next() -> NUIDStr {
     GLOBAL_NUID.lock().unwrap().next()
 }
 
+/// Generate `n` `NUID` strings from the global locked `NUID` instance under a
+/// single lock acquisition, amortizing the locking cost over the whole batch.
+#[allow(clippy::missing_panics_doc)]
+#[allow(clippy::must_use_candidate)]
+pub fn next_batch(n: usize) -> Vec<NUIDStr> {
+    let mut nuid = GLOBAL_NUID.lock().unwrap();
+    (0..n).map(|_| nuid.next()).collect()
+}
+
 /// NUID needs to be very fast to generate and truly unique, all while being entropy pool friendly.
 /// We will use 12 bytes of crypto generated data (entropy draining), and 10 bytes of sequential data
 /// that is started at a pseudo random number and increments with a pseudo-random increment.
 /// Total is 22 bytes of base 62 ascii text :)
-pub struct NUID {
+///
+/// The generator is parameterized over its random number source `R`. The default
+/// [`OsRng`] draws from the operating system entropy pool for the prefix and
+/// also for the sequential start and increment (`thread_rng` is not used), but any
+/// [`RngCore`] can be supplied via [`NUID::from_rng`] — seeding a `StdRng`/`ChaCha`
+/// yields a deterministic, reproducible stream for snapshot testing.
+pub struct NUID<R = OsRng> {
     pre: [u8; PRE_LEN],
     seq: u64,
     inc: u64,
+    rng: R,
+    // state for the time-sortable mode (see `next_sortable`): the last
+    // observed 48-bit millisecond timestamp and the per-millisecond counter
+    // that guarantees strict ordering within a single millisecond.
+    ts: u64,
+    ts_seq: u64,
 }
 
+const TS_LEN: usize = 9; // 62^9 > 2^48, enough for a 48-bit ms timestamp
+const TS_MASK: u64 = (1 << 48) - 1;
+
 /// An `NUID` string.
 ///
 /// Use [`NUIDStr::as_str`], [`NUIDStr::into_bytes`], the [`Deref`] implementation or
 /// [`ToString`] to access the string.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NUIDStr(
     // INVARIANT: this buffer must always contain a valid utf-8 string
     [u8; TOTAL_LEN],
@@ -68,7 +96,7 @@ impl Default for NUID {
     }
 }
 
-impl NUID {
+impl NUID<OsRng> {
     /// generate a new `NUID` and properly initialize the prefix, sequential start, and sequential increment.
     #[must_use]
     pub const fn new() -> Self {
@@ -77,12 +105,111 @@ impl NUID {
             // the first call to `next` will cause the prefix and sequential to be regenerated
             seq: MAX_SEQ,
             inc: 0,
+            rng: OsRng,
+            ts: 0,
+            ts_seq: 0,
+        }
+    }
+
+    /// Derive a stable `NUID` from arbitrary input, like `uuid`'s name-based
+    /// (v3/v5) UUIDs. The same `namespace`/`name` pair always maps to the same
+    /// 22-character ID, which is handy for deduplication and content-addressing
+    /// without keeping a lookup table.
+    ///
+    /// The concatenated bytes are mixed with a fast, dependency-light
+    /// wyhash-style 64-bit hash under several seeds to obtain enough
+    /// pseudo-random bits, which are then expanded into all [`TOTAL_LEN`] base 62
+    /// characters. The result is purely a function of the inputs — no global
+    /// state and no RNG — and is indistinguishable from a generated `NUIDStr`.
+    #[must_use]
+    pub fn from_name(namespace: &[u8], name: &[u8]) -> NUIDStr {
+        let input: Vec<u8> = namespace.iter().chain(name).copied().collect();
+
+        // Mix the namespace length into the seeds so the boundary between
+        // `namespace` and `name` is framed: without this, `(b"a", b"bc")` and
+        // `(b"ab", b"c")` hash the same bytes and collide (cf. uuid v5's
+        // fixed-width namespace).
+        let frame = namespace.len() as u64;
+
+        // Three independently-seeded hashes give 192 bits, comfortably more than
+        // the ~131 bits needed to fill 22 base 62 digits uniformly.
+        let mut limbs = [
+            wyhash(&input, WY0 ^ frame),
+            wyhash(&input, WY1 ^ frame),
+            wyhash(&input, WY2 ^ frame),
+        ];
+
+        let mut buffer = [0u8; TOTAL_LEN];
+        for slot in buffer.iter_mut().rev() {
+            // long division of the 192-bit value (most significant limb first)
+            // by BASE, collecting the remainder as the next base 62 digit.
+            let mut rem: u64 = 0;
+            for limb in &mut limbs {
+                let acc = (u128::from(rem) << 64) | u128::from(*limb);
+                *limb = (acc / BASE as u128) as u64;
+                rem = (acc % BASE as u128) as u64;
+            }
+            *slot = ALPHABET[rem as usize];
+        }
+        // `buffer` is filled from `ALPHABET`, which is always valid utf-8
+        NUIDStr(buffer)
+    }
+}
+
+// wyhash constants and primitives used by `NUID::from_name`.
+const WY0: u64 = 0xa076_1d64_78bd_642f;
+const WY1: u64 = 0xe703_7ed1_a0b4_28db;
+const WY2: u64 = 0x8ebc_6af0_9c88_c6e3;
+const WY3: u64 = 0x5899_65cc_7537_4cc3;
+
+#[inline]
+fn wymum(a: u64, b: u64) -> u64 {
+    let r = u128::from(a) * u128::from(b);
+    (r as u64) ^ ((r >> 64) as u64)
+}
+
+/// A small wyhash-style 64-bit mixing hash: fold the input in 8-byte chunks,
+/// mix the tail, then finalize. Non-cryptographic; used only for deterministic
+/// pseudo-random bit generation.
+fn wyhash(bytes: &[u8], mut seed: u64) -> u64 {
+    seed ^= WY0;
+    let mut chunks = bytes.chunks_exact(8);
+    for chunk in &mut chunks {
+        let k = u64::from_le_bytes(chunk.try_into().unwrap());
+        seed = wymum(seed ^ WY1, k ^ WY2);
+    }
+    let mut tail = 0u64;
+    for (j, &b) in chunks.remainder().iter().enumerate() {
+        tail |= u64::from(b) << (8 * j);
+    }
+    seed = wymum(seed ^ WY3, tail ^ WY1);
+    wymum(seed ^ (bytes.len() as u64), WY0)
+}
+
+impl<R: RngCore> NUID<R> {
+    /// Construct a `NUID` driven by a caller-supplied random number generator.
+    ///
+    /// Passing a seeded generator (e.g. `StdRng::seed_from_u64`) makes the
+    /// produced sequence fully deterministic, which is useful for tests and for
+    /// environments where [`OsRng`]/`thread_rng` are undesirable.
+    pub fn from_rng(rng: R) -> Self {
+        Self {
+            pre: [0; PRE_LEN],
+            // the first call to `next` will cause the prefix and sequential to be regenerated
+            seq: MAX_SEQ,
+            inc: 0,
+            rng,
+            ts: 0,
+            ts_seq: 0,
         }
     }
 
     pub fn randomize_prefix(&mut self) {
-        let rng = OsRng;
-        for (i, n) in rng.sample_iter(&Alphanumeric).take(PRE_LEN).enumerate() {
+        for (i, n) in (&mut self.rng)
+            .sample_iter(&Alphanumeric)
+            .take(PRE_LEN)
+            .enumerate()
+        {
             self.pre[i] = ALPHABET[n as usize % BASE];
         }
     }
@@ -92,35 +219,108 @@ impl NUID {
     #[allow(clippy::must_use_candidate)]
     pub fn next(&mut self) -> NUIDStr {
         let mut buffer = [0u8; TOTAL_LEN];
+        self.next_into(&mut buffer);
+        // `buffer` has been filled with base62 data, which is always valid utf-8
+        NUIDStr(buffer)
+    }
 
+    /// Generate the next `NUID` directly into a caller-provided buffer, writing
+    /// the bytes in place instead of returning a [`NUIDStr`]. The prefix is
+    /// regenerated in place whenever the sequence rolls past [`MAX_SEQ`].
+    pub fn next_into(&mut self, out: &mut [u8; TOTAL_LEN]) {
         self.seq += self.inc;
         if self.seq >= MAX_SEQ {
             self.randomize_prefix();
             self.reset_sequential();
         }
-        let seq: usize = self.seq as usize;
 
-        for (i, n) in self.pre.iter().enumerate() {
-            buffer[i] = *n;
-        }
+        out[..PRE_LEN].copy_from_slice(&self.pre);
 
-        let mut l = seq;
+        let mut l = self.seq as usize;
         for i in (PRE_LEN..TOTAL_LEN).rev() {
-            buffer[i] = ALPHABET[l % BASE];
+            out[i] = ALPHABET[l % BASE];
             l /= BASE;
         }
+    }
 
-        // `buffer` has been filled with base62 data, which is always valid utf-8
+    /// Fill `out` with a batch of `NUID`s, advancing the sequence once per slot.
+    ///
+    /// This emits `out.len()` IDs under a single borrow of the generator,
+    /// amortizing entropy costs (the prefix is only regenerated mid-batch when
+    /// the sequence rolls past [`MAX_SEQ`]); see [`next_batch`] for the global
+    /// equivalent that amortizes the lock acquisition.
+    pub fn fill_batch(&mut self, out: &mut [[u8; TOTAL_LEN]]) {
+        for slot in out.iter_mut() {
+            self.next_into(slot);
+        }
+    }
+
+    /// Generate the next time-sortable `NUID` string.
+    ///
+    /// Unlike [`next`](Self::next), whose random prefix scatters IDs, the strings
+    /// produced here sort lexicographically by creation time — analogous to a
+    /// UUIDv7. The leading [`TS_LEN`] characters encode a 48-bit millisecond
+    /// timestamp big-endian, and the remaining characters carry a per-millisecond
+    /// counter seeded from randomness.
+    ///
+    /// Strict ordering within a single millisecond is guaranteed by the stored
+    /// counter: repeated calls in the same millisecond increment it rather than
+    /// redrawing, and if the counter saturates the timestamp is rolled forward.
+    ///
+    /// Byte-wise string comparison matches timestamp order because [`ALPHABET`]
+    /// is itself strictly ascending in ASCII (`0`..`9` < `A`..`Z` < `a`..`z`), so
+    /// the encoded digit bytes compare in the same order as their numeric values.
+    #[allow(clippy::must_use_candidate)]
+    pub fn next_sortable(&mut self) -> NUIDStr {
+        let now = now_millis() & TS_MASK;
+        if now > self.ts {
+            self.ts = now;
+            self.ts_seq = self.rng.next_u64();
+        } else {
+            // same millisecond, or the clock moved backwards: stay monotonic
+            match self.ts_seq.checked_add(1) {
+                Some(n) => self.ts_seq = n,
+                None => {
+                    self.ts = (self.ts + 1) & TS_MASK;
+                    self.ts_seq = self.rng.next_u64();
+                }
+            }
+        }
+
+        let mut buffer = [0u8; TOTAL_LEN];
+        encode_base62(self.ts, &mut buffer[..TS_LEN]);
+        encode_base62(self.ts_seq, &mut buffer[TS_LEN..]);
+        // `buffer` is filled from `ALPHABET`, which is always valid utf-8
         NUIDStr(buffer)
     }
 
     fn reset_sequential(&mut self) {
-        let mut rng = thread_rng();
-        self.seq = rng.gen_range(0..MAX_SEQ);
-        self.inc = rng.gen_range(MIN_INC..MAX_INC);
+        self.seq = self.rng.gen_range(0..MAX_SEQ);
+        self.inc = self.rng.gen_range(MIN_INC..MAX_INC);
+    }
+}
+
+/// Encode `value` big-endian into `out` using the base 62 [`ALPHABET`].
+///
+/// Only the low `out.len()` base 62 digits are written; higher digits are
+/// silently truncated, which is fine for both the bounded 48-bit timestamp and
+/// the `u64` counter (`u64::MAX < 62^13`).
+fn encode_base62(mut value: u64, out: &mut [u8]) {
+    for slot in out.iter_mut().rev() {
+        *slot = ALPHABET[(value % BASE as u64) as usize];
+        value /= BASE as u64;
     }
 }
 
+/// The number of whole milliseconds elapsed since the Unix epoch, truncated to a
+/// `u64`; 0 if the system clock reads before the epoch.
+fn now_millis() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
 impl NUIDStr {
     /// Get a reference to the inner string
     pub fn as_str(&self) -> &str {
@@ -132,6 +332,91 @@ impl NUIDStr {
     pub fn into_bytes(self) -> [u8; TOTAL_LEN] {
         self.0
     }
+
+    /// Parse a `NUIDStr` from a string slice, validating that it is exactly
+    /// [`TOTAL_LEN`] characters long and that every byte is a member of the
+    /// base 62 alphabet (`0-9A-Za-z`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError::WrongLength`] if the input is not [`TOTAL_LEN`]
+    /// bytes, or [`ParseError::InvalidChar`] if any byte is outside the
+    /// alphabet.
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        Self::from_bytes(s.as_bytes())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
+        if bytes.len() != TOTAL_LEN {
+            return Err(ParseError::WrongLength { found: bytes.len() });
+        }
+        let mut buffer = [0u8; TOTAL_LEN];
+        for (i, &byte) in bytes.iter().enumerate() {
+            if !ALPHABET.contains(&byte) {
+                return Err(ParseError::InvalidChar { index: i, byte });
+            }
+            buffer[i] = byte;
+        }
+        // every byte was verified to be in `ALPHABET`, which is ascii, so the
+        // buffer is valid utf-8
+        Ok(NUIDStr(buffer))
+    }
+}
+
+/// An error returned when parsing a string into a [`NUIDStr`] fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input was not exactly [`TOTAL_LEN`] bytes long.
+    WrongLength {
+        /// The number of bytes that were actually supplied.
+        found: usize,
+    },
+    /// A byte outside the base 62 alphabet was encountered.
+    InvalidChar {
+        /// The position of the offending byte.
+        index: usize,
+        /// The offending byte.
+        byte: u8,
+    },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::WrongLength { found } => {
+                write!(f, "invalid length: expected {TOTAL_LEN} bytes, found {found}")
+            }
+            ParseError::InvalidChar { index, byte } => {
+                write!(f, "invalid character {byte:#04x} at index {index}")
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl FromStr for NUIDStr {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<&[u8]> for NUIDStr {
+    type Error = ParseError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_bytes(bytes)
+    }
+}
+
+impl TryFrom<&str> for NUIDStr {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::parse(s)
+    }
 }
 
 impl Display for NUIDStr {
@@ -149,6 +434,71 @@ impl Deref for NUIDStr {
     }
 }
 
+#[cfg(feature = "serde")]
+mod serde_support {
+    use super::{NUIDStr, TOTAL_LEN};
+    use serde::de::{self, Visitor};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::fmt;
+
+    impl Serialize for NUIDStr {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                serializer.serialize_str(self.as_str())
+            } else {
+                serializer.serialize_bytes(&self.0)
+            }
+        }
+    }
+
+    struct NuidVisitor;
+
+    impl Visitor<'_> for NuidVisitor {
+        type Value = NUIDStr;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "a {TOTAL_LEN}-character base 62 NUID string or byte array")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            NUIDStr::parse(v).map_err(de::Error::custom)
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+            NUIDStr::from_bytes(v).map_err(de::Error::custom)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for NUIDStr {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            if deserializer.is_human_readable() {
+                deserializer.deserialize_str(NuidVisitor)
+            } else {
+                deserializer.deserialize_bytes(NuidVisitor)
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn json_roundtrip() {
+        let id = next();
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, format!("\"{id}\""));
+        let back: NUIDStr = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.as_str(), id.as_str());
+    }
+
+    #[test]
+    fn json_rejects_malformed() {
+        assert!(serde_json::from_str::<NUIDStr>("\"too short\"").is_err());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -209,6 +559,104 @@ mod tests {
         }
     }
 
+    #[test]
+    fn fill_batch_and_next_batch_are_unique() {
+        let mut set = HashSet::new();
+        let mut batch = [[0u8; TOTAL_LEN]; 1_000];
+        let mut n = NUID::new();
+        n.fill_batch(&mut batch);
+        for raw in &batch {
+            assert!(set.insert(NUIDStr(*raw).to_string()));
+        }
+
+        let batch = next_batch(1_000);
+        assert_eq!(batch.len(), 1_000);
+        for id in &batch {
+            assert!(set.insert(id.to_string()));
+        }
+    }
+
+    #[test]
+    fn from_name_is_deterministic() {
+        let a = NUID::from_name(b"ns", b"alice");
+        let b = NUID::from_name(b"ns", b"alice");
+        assert_eq!(a.as_str(), b.as_str());
+        assert_eq!(a.len(), TOTAL_LEN);
+        // validates via the parser, i.e. indistinguishable from a generated id
+        assert!(NUIDStr::parse(a.as_str()).is_ok());
+
+        assert_ne!(
+            NUID::from_name(b"ns", b"alice").as_str(),
+            NUID::from_name(b"ns", b"bob").as_str()
+        );
+        // namespace participates in the hash
+        assert_ne!(
+            NUID::from_name(b"a", b"bc").as_str(),
+            NUID::from_name(b"ab", b"c").as_str()
+        );
+    }
+
+    #[test]
+    fn sortable_is_monotonic() {
+        let mut n = NUID::new();
+        let mut prev = n.next_sortable().to_string();
+        for _ in 0..100_000 {
+            let cur = n.next_sortable().to_string();
+            assert!(cur > prev, "{cur} !> {prev}");
+            assert_eq!(cur.len(), TOTAL_LEN);
+            prev = cur;
+        }
+    }
+
+    #[test]
+    fn seeded_rng_is_deterministic() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut a = NUID::from_rng(StdRng::seed_from_u64(42));
+        let mut b = NUID::from_rng(StdRng::seed_from_u64(42));
+        for _ in 0..1_000 {
+            assert_eq!(a.next().to_string(), b.next().to_string());
+        }
+
+        let mut c = NUID::from_rng(StdRng::seed_from_u64(43));
+        assert_ne!(a.next().to_string(), c.next().to_string());
+    }
+
+    #[test]
+    fn parse_roundtrip() {
+        let id = next();
+        let parsed = NUIDStr::parse(id.as_str()).unwrap();
+        assert_eq!(parsed.as_str(), id.as_str());
+    }
+
+    #[test]
+    fn parse_wrong_length() {
+        assert_eq!(
+            NUIDStr::parse("too short"),
+            Err(ParseError::WrongLength { found: 9 })
+        );
+    }
+
+    #[test]
+    fn parse_invalid_char() {
+        let mut s = next().to_string();
+        s.replace_range(3..4, "-");
+        assert_eq!(
+            NUIDStr::parse(&s),
+            Err(ParseError::InvalidChar { index: 3, byte: b'-' })
+        );
+    }
+
+    #[test]
+    fn try_from_impls() {
+        let id = next();
+        let bytes = id.to_string();
+        assert!(NUIDStr::try_from(bytes.as_str()).is_ok());
+        assert!(NUIDStr::try_from(bytes.as_bytes()).is_ok());
+        assert!("nope".parse::<NUIDStr>().is_err());
+    }
+
     #[test]
     fn unique() {
         let mut set = HashSet::new();